@@ -0,0 +1,128 @@
+use anyhow::Result;
+
+/// Performs the terminal-level setup/teardown (raw mode, alternate screen,
+/// mouse capture, …) needed around a render loop, independent of which
+/// terminal library actually backs rendering.
+pub trait TerminalAdapter {
+    fn enter(&mut self) -> Result<()>;
+    /// Tears the terminal down, returning `true` if it actually left raw
+    /// mode / the alternate screen. `exit` is called both explicitly on
+    /// quit and again from `Drop`, so callers use this to make the second,
+    /// already-torn-down call a no-op.
+    fn exit(&mut self) -> Result<bool>;
+}
+
+#[cfg(feature = "crossterm")]
+pub use self::crossterm_adapter::CrosstermAdapter;
+
+#[cfg(feature = "crossterm")]
+mod crossterm_adapter {
+    use super::TerminalAdapter;
+    use anyhow::Result;
+    use ratatui::crossterm;
+    use ratatui::crossterm::event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    };
+    use ratatui::crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+    use std::io::Write;
+
+    /// Drives terminal setup/teardown through crossterm, writing its escape
+    /// sequences to whichever output stream the backend was built with.
+    pub struct CrosstermAdapter<W: Write> {
+        writer: W,
+    }
+
+    impl<W: Write> CrosstermAdapter<W> {
+        pub fn new(writer: W) -> Self {
+            Self { writer }
+        }
+    }
+
+    impl<W: Write> TerminalAdapter for CrosstermAdapter<W> {
+        fn enter(&mut self) -> Result<()> {
+            crossterm::terminal::enable_raw_mode()?;
+            crossterm::execute!(
+                self.writer,
+                EnterAlternateScreen,
+                EnableMouseCapture,
+                EnableBracketedPaste,
+                EnableFocusChange
+            )?;
+            Ok(())
+        }
+
+        fn exit(&mut self) -> Result<bool> {
+            if crossterm::terminal::is_raw_mode_enabled()? {
+                self.writer.flush()?;
+                crossterm::execute!(
+                    self.writer,
+                    DisableFocusChange,
+                    DisableBracketedPaste,
+                    LeaveAlternateScreen,
+                    DisableMouseCapture
+                )?;
+                crossterm::terminal::disable_raw_mode()?;
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "termion")]
+pub use self::termion_adapter::TermionAdapter;
+
+#[cfg(feature = "termion")]
+mod termion_adapter {
+    use super::TerminalAdapter;
+    use anyhow::Result;
+    use std::io::Write;
+    use std::os::fd::AsFd;
+    use termion::raw::{IntoRawMode, RawTerminal};
+    use termion::screen::{ToAlternateScreen, ToMainScreen};
+
+    /// Drives terminal setup/teardown through termion instead of crossterm,
+    /// for callers that build with `--features termion --no-default-features`.
+    ///
+    /// Unlike `CrosstermAdapter`, the raw-mode guard is kept alive for the
+    /// adapter's whole lifetime and `enter`/`exit` just suspend/reactivate it,
+    /// so an adapter can go through several enter/exit cycles (e.g. the
+    /// suspend-to-shell-and-resume flow in `Tui::suspend`) instead of only
+    /// the first one.
+    pub struct TermionAdapter<W: Write + AsFd> {
+        raw: RawTerminal<W>,
+        active: bool,
+    }
+
+    impl<W: Write + AsFd> TermionAdapter<W> {
+        pub fn new(writer: W) -> Result<Self> {
+            Ok(Self {
+                raw: writer.into_raw_mode()?,
+                active: false,
+            })
+        }
+    }
+
+    impl<W: Write + AsFd> TerminalAdapter for TermionAdapter<W> {
+        fn enter(&mut self) -> Result<()> {
+            self.raw.activate_raw_mode()?;
+            write!(self.raw, "{}", ToAlternateScreen)?;
+            self.raw.flush()?;
+            self.active = true;
+            Ok(())
+        }
+
+        fn exit(&mut self) -> Result<bool> {
+            if !self.active {
+                return Ok(false);
+            }
+            write!(self.raw, "{}", ToMainScreen)?;
+            self.raw.flush()?;
+            self.raw.suspend_raw_mode()?;
+            self.active = false;
+            Ok(true)
+        }
+    }
+}
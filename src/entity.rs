@@ -22,6 +22,16 @@ pub enum Entity {
 pub trait Drawable {
     fn tick(&mut self);
     fn draw(&self, ctx: &mut canvas::Context);
+    /// Keeps the entity's position inside a canvas of the given width/height,
+    /// e.g. after a terminal resize shrinks the available space.
+    fn clamp_to_bounds(&mut self, width: f64, height: f64);
+    /// Whether the given canvas coordinates fall within the entity's shape,
+    /// used to pick an already-placed entity up for dragging.
+    fn hit_test(&self, x: f64, y: f64) -> bool;
+    /// Moves the entity to the given canvas coordinates and zeroes its
+    /// vertical velocity. Later calls to `tick` still apply gravity from the
+    /// new position; physics is not paused for an entity being dragged.
+    fn set_position(&mut self, x: f64, y: f64);
 }
 
 
@@ -42,6 +52,25 @@ impl Drawable for Balloon {
     fn draw(&self, ctx: &mut canvas::Context) {
         ctx.draw(&self.circle);
     }
+
+    fn clamp_to_bounds(&mut self, width: f64, height: f64) {
+        let max_x = (width - self.circle.radius).max(self.circle.radius);
+        let max_y = (height - self.circle.radius).max(self.circle.radius);
+        self.circle.x = self.circle.x.clamp(self.circle.radius, max_x);
+        self.circle.y = self.circle.y.clamp(self.circle.radius, max_y);
+    }
+
+    fn hit_test(&self, x: f64, y: f64) -> bool {
+        let dx = x - self.circle.x;
+        let dy = y - self.circle.y;
+        dx.hypot(dy) <= self.circle.radius
+    }
+
+    fn set_position(&mut self, x: f64, y: f64) {
+        self.circle.x = x;
+        self.circle.y = y;
+        self.velocity_y = 0.0;
+    }
 }
 
 impl Drawable for Entity {
@@ -58,6 +87,27 @@ impl Drawable for Entity {
             Entity::Brick(brick) => brick.draw(ctx),
         }
     }
+
+    fn clamp_to_bounds(&mut self, width: f64, height: f64) {
+        match self {
+            Entity::Balloon(balloon) => balloon.clamp_to_bounds(width, height),
+            Entity::Brick(brick) => brick.clamp_to_bounds(width, height),
+        }
+    }
+
+    fn hit_test(&self, x: f64, y: f64) -> bool {
+        match self {
+            Entity::Balloon(balloon) => balloon.hit_test(x, y),
+            Entity::Brick(brick) => brick.hit_test(x, y),
+        }
+    }
+
+    fn set_position(&mut self, x: f64, y: f64) {
+        match self {
+            Entity::Balloon(balloon) => balloon.set_position(x, y),
+            Entity::Brick(brick) => brick.set_position(x, y),
+        }
+    }
 }
 
 
@@ -78,5 +128,93 @@ impl Drawable for Brick {
     fn draw(&self, ctx: &mut canvas::Context) {
         ctx.draw(&self.rectangle);
     }
+
+    fn clamp_to_bounds(&mut self, width: f64, height: f64) {
+        let max_x = (width - self.rectangle.width).max(0.0);
+        let max_y = (height - self.rectangle.height).max(0.0);
+        self.rectangle.x = self.rectangle.x.clamp(0.0, max_x);
+        self.rectangle.y = self.rectangle.y.clamp(0.0, max_y);
+    }
+
+    fn hit_test(&self, x: f64, y: f64) -> bool {
+        x >= self.rectangle.x
+            && x <= self.rectangle.x + self.rectangle.width
+            && y >= self.rectangle.y
+            && y <= self.rectangle.y + self.rectangle.height
+    }
+
+    fn set_position(&mut self, x: f64, y: f64) {
+        self.rectangle.x = x;
+        self.rectangle.y = y;
+        self.velocity_y = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balloon(x: f64, y: f64, radius: f64) -> Balloon {
+        Balloon {
+            circle: Circle { x, y, radius, color: ratatui::style::Color::Blue },
+            velocity_y: 0.0,
+        }
+    }
+
+    fn brick(x: f64, y: f64, width: f64, height: f64) -> Brick {
+        Brick {
+            rectangle: Rectangle { x, y, width, height, color: ratatui::style::Color::Red },
+            velocity_y: 0.0,
+        }
+    }
+
+    #[test]
+    fn balloon_hit_test_boundary() {
+        let b = balloon(5.0, 5.0, 2.0);
+        assert!(b.hit_test(5.0, 5.0)); // center
+        assert!(b.hit_test(7.0, 5.0)); // exactly on the radius
+        assert!(!b.hit_test(7.1, 5.0)); // just outside the radius
+    }
+
+    #[test]
+    fn brick_hit_test_boundary() {
+        let b = brick(2.0, 3.0, 4.0, 1.0);
+        assert!(b.hit_test(2.0, 3.0)); // bottom-left corner
+        assert!(b.hit_test(6.0, 4.0)); // top-right corner
+        assert!(!b.hit_test(6.1, 4.0)); // just past the right edge
+        assert!(!b.hit_test(2.0, 2.9)); // just below the bottom edge
+    }
+
+    #[test]
+    fn balloon_clamp_to_bounds_does_not_panic_when_canvas_smaller_than_entity() {
+        let mut b = balloon(50.0, 50.0, 1.0);
+        b.clamp_to_bounds(0.5, 0.5);
+        assert_eq!(b.circle.x, 1.0);
+        assert_eq!(b.circle.y, 1.0);
+    }
+
+    #[test]
+    fn brick_clamp_to_bounds_does_not_panic_when_canvas_smaller_than_entity() {
+        let mut b = brick(50.0, 50.0, 4.0, 3.0);
+        b.clamp_to_bounds(1.0, 1.0);
+        assert_eq!(b.rectangle.x, 0.0);
+        assert_eq!(b.rectangle.y, 0.0);
+    }
+
+    #[test]
+    fn balloon_clamp_to_bounds_keeps_position_within_a_normal_canvas() {
+        let mut b = balloon(-10.0, 200.0, 1.0);
+        b.clamp_to_bounds(20.0, 20.0);
+        assert_eq!(b.circle.x, 1.0);
+        assert_eq!(b.circle.y, 19.0);
+    }
+
+    #[test]
+    fn set_position_zeroes_velocity() {
+        let mut b = balloon(0.0, 0.0, 1.0);
+        b.velocity_y = 3.5;
+        b.set_position(4.0, 6.0);
+        assert_eq!((b.circle.x, b.circle.y), (4.0, 6.0));
+        assert_eq!(b.velocity_y, 0.0);
+    }
 }
-// write some test
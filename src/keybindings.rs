@@ -0,0 +1,102 @@
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Actions a key chord can be bound to, as loaded from the keybindings config.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Suspend,
+}
+
+pub type KeyBindings = HashMap<KeyEvent, Action>;
+
+/// The bindings used when no config file is present.
+pub fn default_keybindings() -> KeyBindings {
+    let mut bindings = KeyBindings::new();
+    bindings.insert(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), Action::Quit);
+    bindings.insert(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE), Action::Quit);
+    bindings.insert(
+        KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+        Action::Quit,
+    );
+    bindings.insert(
+        KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL),
+        Action::Suspend,
+    );
+    bindings
+}
+
+/// Loads a keybindings config (RON) mapping key chords like `<q>` or
+/// `<Ctrl-z>` to an [`Action`]. Falls back to [`default_keybindings`] if the
+/// file does not exist.
+pub fn load_keybindings(path: &Path) -> Result<KeyBindings> {
+    if !path.exists() {
+        return Ok(default_keybindings());
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read keybindings config at {}", path.display()))?;
+    let raw: HashMap<String, Action> = ron::de::from_str(&contents)
+        .with_context(|| format!("Failed to parse keybindings config at {}", path.display()))?;
+
+    raw.into_iter()
+        .map(|(chord, action)| parse_key_chord(&chord).map(|key| (key, action)))
+        .collect()
+}
+
+fn parse_key_chord(chord: &str) -> Result<KeyEvent> {
+    let inner = chord.trim().trim_start_matches('<').trim_end_matches('>');
+    let mut parts: Vec<&str> = inner.split('-').collect();
+    let key_part = parts.pop().with_context(|| format!("empty key chord `{chord}`"))?;
+
+    let mut modifiers = KeyModifiers::NONE;
+    for modifier in parts {
+        modifiers |= match modifier.to_ascii_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            other => bail!("unknown modifier `{other}` in key chord `{chord}`"),
+        };
+    }
+
+    let code = match key_part.to_ascii_lowercase().as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        single if single.chars().count() == 1 => KeyCode::Char(key_part.chars().next().unwrap()),
+        other => bail!("unknown key `{other}` in key chord `{chord}`"),
+    };
+
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_key_chord_plain_char() {
+        let key = parse_key_chord("<q>").unwrap();
+        assert_eq!(key, KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn parse_key_chord_with_modifier() {
+        let key = parse_key_chord("<Ctrl-z>").unwrap();
+        assert_eq!(key, KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL));
+    }
+
+    #[test]
+    fn parse_key_chord_rejects_unknown_modifier() {
+        assert!(parse_key_chord("<Foo-q>").is_err());
+    }
+
+    #[test]
+    fn parse_key_chord_rejects_unknown_key() {
+        assert!(parse_key_chord("<banana>").is_err());
+    }
+}
@@ -1,21 +1,49 @@
-use anyhow::{Context, Result};
+//! Input polling goes through crossterm's [`EventStream`] regardless of
+//! which backend feature is enabled — `crossterm` is a mandatory
+//! dependency for that reason. The `crossterm`/`termion` features only
+//! pick which backend *renders* the UI (and which library owns raw
+//! mode/the alternate screen): `crossterm` gates [`TuiBuilder::stdout`]
+//! and [`TuiBuilder::stderr`], `termion` gates
+//! [`TuiBuilder::termion_stdout`]. There is currently no way to poll
+//! input without crossterm, even when only the `termion` feature is
+//! enabled.
+
+use anyhow::Context;
+pub use anyhow::Result;
+use crossterm::event::{Event, EventStream, KeyEventKind, MouseButton, MouseEventKind};
+#[cfg(feature = "crossterm")]
+use crossterm::event::{DisableBracketedPaste, DisableFocusChange, DisableMouseCapture};
+#[cfg(feature = "crossterm")]
+use crossterm::terminal::LeaveAlternateScreen;
+use futures::{FutureExt, StreamExt};
 use rand::prelude::*;
+use ratatui::backend::Backend;
+#[cfg(feature = "crossterm")]
 use ratatui::backend::CrosstermBackend;
-use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
-use ratatui::crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::style::Color::{Black, Blue, Red};
 use ratatui::symbols::Marker;
 use ratatui::widgets::canvas::{Canvas, Circle, Rectangle};
 use ratatui::widgets::Block;
-use ratatui::{crossterm, Terminal};
+use ratatui::Terminal;
+use std::io::Write;
+use std::path::Path;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::time;
+use tokio_util::sync::CancellationToken;
+mod backend;
 mod fps_counter;
 mod entity;
+mod keybindings;
 
+use crate::backend::TerminalAdapter;
+#[cfg(feature = "crossterm")]
+use crate::backend::CrosstermAdapter;
+#[cfg(feature = "termion")]
+use crate::backend::TermionAdapter;
 use crate::entity::{Balloon, Brick, Drawable, Entity};
+use crate::keybindings::{load_keybindings, Action, KeyBindings};
 use fps_counter::FpsCounter;
 
 
@@ -23,46 +51,198 @@ pub struct Model {
     pub hover_pos: (u16, u16),
     pub entities: Vec<Entity>,
     pub hover_entity: Entity,
-    pub fps_counter: FpsCounter
+    pub fps_counter: FpsCounter,
+    pub canvas_size: (u16, u16),
+    pub keybindings: KeyBindings,
+    pub dragging: Option<usize>,
 }
 #[derive(Clone, Debug)]
 pub enum Message {
     Quit,
+    Suspend,
     Tick,
     Render,
     MouseLeftClick(u16, u16),
     MouseHoverPos(u16, u16),
+    MouseDrag(u16, u16),
+    MouseRelease,
+    Resize(u16, u16),
+    FocusGained,
+    FocusLost,
+    Paste(String),
 }
 
-pub struct Tui {
-    pub terminal: Terminal<CrosstermBackend<std::io::Stdout>>,
+pub struct Tui<B: Backend, A: TerminalAdapter> {
+    pub terminal: Terminal<B>,
+    pub adapter: A,
     pub frame_rate: f64,
     pub tick_rate: f64,
     pub event_tx: UnboundedSender<Message>,
     pub event_rx: UnboundedReceiver<Message>,
     pub model: Model,
+    pub cancellation_token: CancellationToken,
 }
 
 #[derive(Clone, Debug)]
 pub enum UpdateCommand {
     None,
     Quit,
+    Suspend,
+}
+
+/// Selects which output stream a [`Tui`] renders to, so stdout can be left
+/// free for piping the program's own data while the UI draws elsewhere.
+pub struct TuiBuilder {
+    frame_rate: f64,
+    tick_rate: f64,
+}
+
+impl TuiBuilder {
+    pub fn new(frame_rate: f64, tick_rate: f64) -> Self {
+        Self { frame_rate, tick_rate }
+    }
+
+    /// Renders to stdout. This is also what [`Tui::new`] uses. Installs a
+    /// panic hook that restores stdout (raw mode, alternate screen, mouse
+    /// capture, cursor) before handing off to the previously installed hook,
+    /// so a panic while the TUI is running doesn't leave the shell broken.
+    #[cfg(feature = "crossterm")]
+    pub fn stdout(self) -> Result<Tui<CrosstermBackend<std::io::Stdout>, CrosstermAdapter<std::io::Stdout>>> {
+        install_crossterm_panic_hook(restore_crossterm_stdout);
+        Tui::with_writer(self.frame_rate, self.tick_rate, std::io::stdout(), std::io::stdout())
+    }
+
+    /// Renders to stderr, keeping stdout free for the program's own output.
+    /// Installs a panic hook that restores stderr instead of stdout.
+    #[cfg(feature = "crossterm")]
+    pub fn stderr(self) -> Result<Tui<CrosstermBackend<std::io::Stderr>, CrosstermAdapter<std::io::Stderr>>> {
+        install_crossterm_panic_hook(restore_crossterm_stderr);
+        Tui::with_writer(self.frame_rate, self.tick_rate, std::io::stderr(), std::io::stderr())
+    }
+
+    /// Renders to stdout through termion instead of crossterm. Installs a
+    /// panic hook that restores the terminal through termion.
+    #[cfg(feature = "termion")]
+    pub fn termion_stdout(
+        self,
+    ) -> Result<Tui<ratatui::backend::TermionBackend<std::io::Stdout>, TermionAdapter<std::io::Stdout>>> {
+        install_termion_panic_hook();
+        Tui::with_termion_writer(self.frame_rate, self.tick_rate, std::io::stdout(), std::io::stdout())
+    }
+}
+
+/// Installs a panic hook that restores `restore`'s stream before handing off
+/// to the previously installed hook. Called from each [`TuiBuilder`] output
+/// method with the restore function matching what that method actually set
+/// up, so the hook can never end up paired with the wrong stream the way two
+/// freestanding install-hook methods could be if a caller picked the wrong
+/// one.
+#[cfg(feature = "crossterm")]
+fn install_crossterm_panic_hook(restore: fn() -> Result<()>) {
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let _ = restore();
+        original_hook(panic_info);
+    }));
+}
+
+#[cfg(feature = "crossterm")]
+fn restore_crossterm_stdout() -> Result<()> {
+    crossterm::terminal::disable_raw_mode()?;
+    crossterm::execute!(
+        std::io::stdout(),
+        DisableFocusChange,
+        DisableBracketedPaste,
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    crossterm::execute!(std::io::stdout(), crossterm::cursor::Show)?;
+    Ok(())
+}
+
+#[cfg(feature = "crossterm")]
+fn restore_crossterm_stderr() -> Result<()> {
+    crossterm::terminal::disable_raw_mode()?;
+    crossterm::execute!(
+        std::io::stderr(),
+        DisableFocusChange,
+        DisableBracketedPaste,
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    crossterm::execute!(std::io::stderr(), crossterm::cursor::Show)?;
+    Ok(())
 }
 
-impl Tui {
+/// Same idea as [`install_crossterm_panic_hook`], for the termion backend.
+/// Termion's own `RawTerminal` restores the terminal mode via its `Drop` impl
+/// once `TermionAdapter` unwinds, so this only needs to leave the alternate
+/// screen and show the cursor before that happens.
+#[cfg(feature = "termion")]
+fn install_termion_panic_hook() {
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let _ = restore_termion_stdout();
+        original_hook(panic_info);
+    }));
+}
+
+#[cfg(feature = "termion")]
+fn restore_termion_stdout() -> Result<()> {
+    let mut out = std::io::stdout();
+    write!(out, "{}", termion::screen::ToMainScreen)?;
+    write!(out, "{}", termion::cursor::Show)?;
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(feature = "crossterm")]
+impl Tui<CrosstermBackend<std::io::Stdout>, CrosstermAdapter<std::io::Stdout>> {
     pub fn new(frame_rate: f64, tick_rate: f64) -> Result<Self> {
-        let terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+        TuiBuilder::new(frame_rate, tick_rate).stdout()
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl<W: Write> Tui<CrosstermBackend<W>, CrosstermAdapter<W>> {
+    fn with_writer(frame_rate: f64, tick_rate: f64, backend_writer: W, adapter_writer: W) -> Result<Self> {
+        let terminal = Terminal::new(CrosstermBackend::new(backend_writer))?;
+        let adapter = CrosstermAdapter::new(adapter_writer);
+        Self::assemble(terminal, adapter, frame_rate, tick_rate)
+    }
+}
+
+#[cfg(feature = "termion")]
+impl<W: Write + std::os::fd::AsFd> Tui<ratatui::backend::TermionBackend<W>, TermionAdapter<W>> {
+    fn with_termion_writer(frame_rate: f64, tick_rate: f64, backend_writer: W, adapter_writer: W) -> Result<Self> {
+        let terminal = Terminal::new(ratatui::backend::TermionBackend::new(backend_writer))?;
+        let adapter = TermionAdapter::new(adapter_writer)?;
+        Self::assemble(terminal, adapter, frame_rate, tick_rate)
+    }
+}
+
+impl<B: Backend, A: TerminalAdapter> Tui<B, A> {
+    /// Builds a `Tui` from an already-constructed backend/adapter pair,
+    /// shared by every output-target constructor (stdout, stderr, termion, …).
+    fn assemble(terminal: Terminal<B>, adapter: A, frame_rate: f64, tick_rate: f64) -> Result<Self> {
+        let canvas_size = terminal.size().map(|s| (s.width, s.height))?;
+        let keybindings = load_keybindings(Path::new("keybindings.ron"))?;
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         Ok(Self {
             terminal,
+            adapter,
             frame_rate,
             tick_rate,
             event_tx,
             event_rx,
+            cancellation_token: CancellationToken::new(),
             model: Model {
                 hover_pos: (0, 0),
                 entities: Vec::new(),
                 fps_counter: FpsCounter::new(),
+                canvas_size,
+                keybindings,
+                dragging: None,
                 hover_entity: {
                     Entity::Balloon(
                         Balloon {
@@ -79,30 +259,50 @@ impl Tui {
             },
         })
     }
+
     fn enter(&mut self) -> Result<()> {
-        crossterm::terminal::enable_raw_mode()?;
-        crossterm::execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
-        Ok(())
+        self.adapter.enter()
     }
 
     pub fn exit(&mut self) -> Result<()> {
-        if crossterm::terminal::is_raw_mode_enabled()? {
-            self.terminal.flush()?;
-            crossterm::execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
-            crossterm::terminal::disable_raw_mode()?;
+        self.terminal.flush()?;
+        if self.adapter.exit()? {
             self.terminal.show_cursor()?;
             println!("Terminal exited.");
         }
         Ok(())
     }
+    /// Leaves the alternate screen, stops the process with `SIGTSTP`, and
+    /// restores the TUI once the shell resumes it with `SIGCONT`.
+    fn suspend(&mut self) -> Result<()> {
+        self.adapter.exit()?;
+        self.terminal.show_cursor()?;
+
+        unsafe {
+            libc::raise(libc::SIGTSTP);
+        }
+
+        self.adapter.enter()?;
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         self.enter()?;
         let tick_rate = Duration::from_secs_f64(1.0 / self.tick_rate);
         let frame_rate = Duration::from_secs_f64(1.0 / self.frame_rate);
         let mut tick_interval = time::interval(tick_rate);
         let mut frame_interval = time::interval(frame_rate);
+        let mut reader = EventStream::new();
         loop {
+            let crossterm_event = reader.next().fuse();
             tokio::select! {
+                _ = self.cancellation_token.cancelled() => {
+                    return {
+                        self.exit()?;
+                        Ok(())
+                    };
+                }
                 _tick = tick_interval.tick() => {
                     if let Err(e) = self.event_tx.send(Message::Tick) {
                         return Err(anyhow::anyhow!("Failed to tick: {:?}", e));
@@ -115,25 +315,28 @@ impl Tui {
                 }
                 Some(message) = self.event_rx.recv() => {
                     match self.update(message).await? {
-                        UpdateCommand::Quit => return {
-                            self.exit()?;
-                            Ok(())
-                        },
+                        UpdateCommand::Quit => {
+                            self.cancellation_token.cancel();
+                        }
+                        UpdateCommand::Suspend => {
+                            self.suspend()?;
+                        }
                         UpdateCommand::None => continue,
                     }
                 }
-                Ok(ready) = tokio::task::spawn_blocking(|| crossterm::event::poll(Duration::from_millis(100))) => {
-                    match ready {
-                        Ok(true) => {
-                            let event = crossterm::event::read()?;
+                maybe_event = crossterm_event => {
+                    match maybe_event {
+                        Some(Ok(event)) => {
                             if let Err(e) = self.handle_event(event) {
                                 return Err(anyhow::anyhow!("Failed to handle event: {:?}", e));
                             }
                         }
-                        Ok(false) => continue,
-                        Err(e) => {
-                                return Err(anyhow::anyhow!("Failed to poll for events: {:?}", e));
-                            }
+                        Some(Err(e)) => {
+                            return Err(anyhow::anyhow!("Failed to read event: {:?}", e));
+                        }
+                        None => {
+                            self.cancellation_token.cancel();
+                        }
                     }
                 }
             }
@@ -143,24 +346,44 @@ impl Tui {
     fn handle_event(&self, event: Event) -> Result<()> {
         match event {
             Event::Key(key) => {
-                if key.kind == KeyEventKind::Press  && key.code == KeyCode::Esc{
-                    self.event_tx.send(Message::Quit)?;
+                if key.kind == KeyEventKind::Press {
+                    if let Some(action) = self.model.keybindings.get(&key) {
+                        match action {
+                            Action::Quit => self.event_tx.send(Message::Quit)?,
+                            Action::Suspend => self.event_tx.send(Message::Suspend)?,
+                        }
+                    }
                 }
             }
             Event::Mouse(mouse) => {
                 match mouse.kind {
-                    MouseEventKind::Down(mb) => {
-                        if mb == MouseButton::Left {
-                            self.event_tx.send(Message::MouseLeftClick(mouse.row, mouse.column))?;
-                        }
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        self.event_tx.send(Message::MouseLeftClick(mouse.row, mouse.column))?;
                     }
                     MouseEventKind::Moved => {
                         self.event_tx.send(Message::MouseHoverPos(mouse.row, mouse.column))?;
                     }
+                    MouseEventKind::Drag(MouseButton::Left) => {
+                        self.event_tx.send(Message::MouseDrag(mouse.row, mouse.column))?;
+                    }
+                    MouseEventKind::Up(MouseButton::Left) => {
+                        self.event_tx.send(Message::MouseRelease)?;
+                    }
                     _ => {}
                 }
             }
-            _ => {}
+            Event::Resize(width, height) => {
+                self.event_tx.send(Message::Resize(width, height))?;
+            }
+            Event::FocusGained => {
+                self.event_tx.send(Message::FocusGained)?;
+            }
+            Event::FocusLost => {
+                self.event_tx.send(Message::FocusLost)?;
+            }
+            Event::Paste(text) => {
+                self.event_tx.send(Message::Paste(text))?;
+            }
         }
         Ok(())
     }
@@ -170,6 +393,9 @@ impl Tui {
             Message::Quit => {
                 Ok(UpdateCommand::Quit)
             }
+            Message::Suspend => {
+                Ok(UpdateCommand::Suspend)
+            }
             Message::Tick => {
                 for obj in &mut self.model.entities {
                     obj.tick();
@@ -183,7 +409,21 @@ impl Tui {
             }
             Message::MouseLeftClick(row, col) => {
                 let x = col as f64;
-                let y = self.terminal.size()?.height as f64 - row as f64;
+                let y = self.model.canvas_size.1 as f64 - row as f64;
+
+                if let Some(index) = self
+                    .model
+                    .entities
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find(|(_, entity)| entity.hit_test(x, y))
+                    .map(|(index, _)| index)
+                {
+                    self.model.dragging = Some(index);
+                    self.model.entities[index].set_position(x, y);
+                    return Ok(UpdateCommand::None);
+                }
 
                 let clicked_entity = self.model.hover_entity.clone();
                 self.model.entities.push(clicked_entity);
@@ -217,22 +457,51 @@ impl Tui {
             Message::MouseHoverPos(row, col) => {
 
                 self.model.hover_pos = (row, col);
+                let canvas_height = self.model.canvas_size.1 as f64;
                 match &mut self.model.hover_entity {
                     Entity::Balloon(balloon) => {
                         balloon.circle.x = col as f64;
-                        balloon.circle.y = self.terminal.size()?.height as f64 - row as f64; //invert to match canvas coord system
+                        balloon.circle.y = canvas_height - row as f64; //invert to match canvas coord system
                     }
                     Entity::Brick(brick) => {
                         brick.rectangle.x = col as f64;
-                        brick.rectangle.y = self.terminal.size()?.height as f64 - row as f64;
+                        brick.rectangle.y = canvas_height - row as f64;
                     }
                 }
                 Ok(UpdateCommand::None)
             }
+            Message::MouseDrag(row, col) => {
+                if let Some(index) = self.model.dragging {
+                    let x = col as f64;
+                    let y = self.model.canvas_size.1 as f64 - row as f64;
+                    if let Some(entity) = self.model.entities.get_mut(index) {
+                        entity.set_position(x, y);
+                    }
+                }
+                Ok(UpdateCommand::None)
+            }
+            Message::MouseRelease => {
+                self.model.dragging = None;
+                Ok(UpdateCommand::None)
+            }
+            Message::Resize(width, height) => {
+                self.model.canvas_size = (width, height);
+                for entity in &mut self.model.entities {
+                    entity.clamp_to_bounds(width as f64, height as f64);
+                }
+                self.model.hover_entity.clamp_to_bounds(width as f64, height as f64);
+                Ok(UpdateCommand::None)
+            }
+            Message::FocusGained | Message::FocusLost => {
+                Ok(UpdateCommand::None)
+            }
+            Message::Paste(_text) => {
+                Ok(UpdateCommand::None)
+            }
         }
     }
     fn view(&mut self) -> Result<()> {
-        let (term_width, term_height) = self.terminal.size().map(|s| (s.width, s.height))?;
+        let (term_width, term_height) = self.model.canvas_size;
 
         self.terminal.draw(|f| {
             let screen_area = f.area();
@@ -271,7 +540,7 @@ impl Tui {
 }
 
 
-impl Drop for Tui {
+impl<B: Backend, A: TerminalAdapter> Drop for Tui<B, A> {
     fn drop(&mut self) {
         self.exit().expect("Failed to end terminal mode");
     }
@@ -11,7 +11,7 @@ async fn main() -> Result<()> {
 
 pub async fn run_app() -> Result<()> {
     let mut app = Tui::new(30.0, 10.0)
-        .map_err(|e| format!("Failed to initialize the terminal user interface. {}", e))?;
+        .map_err(|e| anyhow::anyhow!("Failed to initialize the terminal user interface. {}", e))?;
     app.run().await?;
     Ok(())
 }